@@ -1,19 +1,30 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-fn get_pkg_config_cflags(lib: &str) -> Vec<String> {
-    let output = Command::new("pkg-config")
-        .args(&["--cflags", lib])
+/// A CPU architecture tauri-spy can produce an injection library for.
+struct Arch {
+    /// Value of the `arch` component in the `libspy-<abi>-<arch>.so` name, and the
+    /// Rust `CARGO_CFG_TARGET_ARCH` spelling.
+    name: &'static str,
+    /// C compiler to use; the host `gcc` when building for the host, otherwise a
+    /// cross compiler such as `aarch64-linux-gnu-gcc`.
+    cc: &'static str,
+    /// pkg-config variant to invoke — the cross builds look up libraries in the
+    /// target sysroot, so they need the per-arch `<arch>-linux-gnu-pkg-config`.
+    pkg_config: &'static str,
+}
+
+/// Run pkg-config (possibly the cross variant) to get `--cflags` for `lib`.
+fn pkg_config_cflags_with(pkg_config: &str, lib: &str) -> Vec<String> {
+    let output = Command::new(pkg_config)
+        .args(["--cflags", lib])
         .output()
-        .unwrap_or_else(|_| panic!("Failed to run pkg-config for {} — is pkg-config installed?", lib));
+        .unwrap_or_else(|_| panic!("Failed to run {} for {}", pkg_config, lib));
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        panic!(
-            "pkg-config --cflags {} failed: {}",
-            lib, stderr
-        );
+        panic!("{} --cflags {} failed: {}", pkg_config, lib, stderr);
     }
 
     String::from_utf8_lossy(&output.stdout)
@@ -23,32 +34,28 @@ fn get_pkg_config_cflags(lib: &str) -> Vec<String> {
         .collect()
 }
 
-fn main() {
-    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
-    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
-    let spy_c = manifest_dir.join("inject").join("spy.c");
-
-    // Get the target profile output directory (where the final binary goes)
-    // OUT_DIR is something like target/release/build/tauri-spy-xxx/out
-    // We want to place libspy.so next to the final binary in target/release/
-    let target_dir = out_dir
-        .ancestors()
-        .find(|p| p.ends_with("release") || p.ends_with("debug"))
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| out_dir.clone());
-
-    let output = target_dir.join("libspy.so");
+/// Returns true if the given pkg-config knows about `lib`.
+fn pkg_config_exists_with(pkg_config: &str, lib: &str) -> bool {
+    Command::new(pkg_config)
+        .args(["--exists", lib])
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
 
-    // Get include flags from pkg-config for WebKitGTK and GTK3
+/// Compile `inject/spy.c` for `arch` against the given WebKitGTK pkg-config
+/// package into `output`.
+fn build_variant(spy_c: &Path, arch: &Arch, webkit_pkg: &str, output: &Path) {
+    // Get include flags from (the arch's) pkg-config for WebKitGTK and GTK3
     let mut cflags: Vec<String> = Vec::new();
-    cflags.extend(get_pkg_config_cflags("webkit2gtk-4.1"));
-    cflags.extend(get_pkg_config_cflags("gtk+-3.0"));
+    cflags.extend(pkg_config_cflags_with(arch.pkg_config, webkit_pkg));
+    cflags.extend(pkg_config_cflags_with(arch.pkg_config, "gtk+-3.0"));
 
     // Deduplicate flags
     cflags.sort();
     cflags.dedup();
 
-    // Compile spy.c into libspy.so
+    // Compile spy.c into the variant's shared object
     let mut gcc_args: Vec<String> = vec![
         "-shared".to_string(),
         "-fPIC".to_string(),
@@ -64,18 +71,118 @@ fn main() {
         "-O2".to_string(),
     ]);
 
-    let status = Command::new("gcc")
+    let status = Command::new(arch.cc)
         .args(&gcc_args)
         .status()
-        .expect("Failed to run gcc — is gcc installed?");
+        .unwrap_or_else(|_| {
+            panic!(
+                "Failed to run {} — is the {} toolchain installed?",
+                arch.cc, arch.name
+            )
+        });
 
     if !status.success() {
-        panic!("Failed to compile inject/spy.c into libspy.so");
+        panic!("Failed to compile inject/spy.c into {}", output.display());
     }
 
-    println!("cargo:rerun-if-changed=inject/spy.c");
     println!(
-        "cargo:warning=libspy.so built at {}",
+        "cargo:warning={} built at {}",
+        output.file_name().unwrap().to_string_lossy(),
         output.display()
     );
 }
+
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let spy_c = manifest_dir.join("inject").join("spy.c");
+
+    // Get the target profile output directory (where the final binary goes)
+    // OUT_DIR is something like target/release/build/tauri-spy-xxx/out
+    // We want to place the libspy variants next to the final binary in target/release/
+    let target_dir = out_dir
+        .ancestors()
+        .find(|p| p.ends_with("release") || p.ends_with("debug"))
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| out_dir.clone());
+
+    // Only build for the arch cargo is targeting. On the host that is plain `gcc`
+    // with the system pkg-config; for a cross target we select the matching cross
+    // compiler and per-arch pkg-config so libraries resolve in the target sysroot.
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_else(|_| "x86_64".to_string());
+    let host = env::var("HOST").unwrap_or_default();
+    let is_host_build = host.starts_with(&format!("{}-", target_arch));
+
+    let arch = match target_arch.as_str() {
+        "x86_64" if is_host_build => Arch {
+            name: "x86_64",
+            cc: "gcc",
+            pkg_config: "pkg-config",
+        },
+        "x86_64" => Arch {
+            name: "x86_64",
+            cc: "x86_64-linux-gnu-gcc",
+            pkg_config: "x86_64-linux-gnu-pkg-config",
+        },
+        "aarch64" if is_host_build => Arch {
+            name: "aarch64",
+            cc: "gcc",
+            pkg_config: "pkg-config",
+        },
+        "aarch64" => Arch {
+            name: "aarch64",
+            cc: "aarch64-linux-gnu-gcc",
+            pkg_config: "aarch64-linux-gnu-pkg-config",
+        },
+        other => panic!("Unsupported target architecture: {other} (x86_64 and aarch64 only)"),
+    };
+
+    // Tauri apps in the wild link against either the older webkit2gtk-4.0 ABI
+    // (libsoup2) or the newer webkit2gtk-4.1 ABI (libsoup3). Build a per-arch
+    // variant for every ABI whose development package is installed so the
+    // launcher can pick the `libspy-<abi>-<arch>.so` that matches the target.
+    let abis = ["webkit2gtk-4.0", "webkit2gtk-4.1"];
+
+    // (abi, arch, absolute path) for every variant we actually built, so the main
+    // crate can embed the bytes via include_bytes! and extract them at runtime.
+    let mut built: Vec<(String, String, PathBuf)> = Vec::new();
+    for webkit_pkg in abis {
+        let abi = webkit_pkg.trim_start_matches("webkit2gtk-");
+        let so_name = format!("libspy-{}-{}.so", abi, arch.name);
+        let output = target_dir.join(&so_name);
+        if pkg_config_exists_with(arch.pkg_config, webkit_pkg) {
+            build_variant(&spy_c, &arch, webkit_pkg, &output);
+            built.push((abi.to_string(), arch.name.to_string(), output));
+        } else {
+            println!("cargo:warning={} not found — skipping {}", webkit_pkg, so_name);
+        }
+    }
+
+    if built.is_empty() {
+        panic!(
+            "Neither webkit2gtk-4.0 nor webkit2gtk-4.1 found for {} — install one of \
+             libwebkit2gtk-4.0-dev or libwebkit2gtk-4.1-dev",
+            arch.name
+        );
+    }
+
+    // Emit a generated source file that embeds the built variants. The main crate
+    // `include!`s it to get an EMBEDDED_LIBSPY table of (abi, arch, bytes).
+    let mut embedded = String::from(
+        "// @generated by build.rs — embedded libspy variants.\n\
+         pub static EMBEDDED_LIBSPY: &[(&str, &str, &[u8])] = &[\n",
+    );
+    for (abi, arch_name, path) in &built {
+        embedded.push_str(&format!(
+            "    ({:?}, {:?}, include_bytes!({:?})),\n",
+            abi,
+            arch_name,
+            path.to_str().expect("non-UTF-8 libspy path")
+        ));
+    }
+    embedded.push_str("];\n");
+    std::fs::write(out_dir.join("embedded_libspy.rs"), embedded)
+        .expect("failed to write embedded_libspy.rs");
+
+    println!("cargo:rerun-if-changed=inject/spy.c");
+}
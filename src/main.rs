@@ -1,14 +1,36 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use colored::Colorize;
 use std::env;
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::process::{Command, ExitCode};
 
 /// Enable WebKitGTK DevTools in Tauri release builds
 #[derive(Parser)]
 #[command(name = "tauri-spy", version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    /// Arguments for the default `run` subcommand (launching a target).
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Launch a target app with DevTools enabled (the default action)
+    Run(RunArgs),
+    /// Diagnose the environment for injection problems
+    Doctor,
+    /// Copy the built libspy variants to a stable install location
+    Install,
+}
+
+#[derive(Args)]
+struct RunArgs {
     /// Path to the target Tauri application binary
     target: PathBuf,
 
@@ -16,33 +38,183 @@ struct Cli {
     #[arg(long)]
     auto_open: bool,
 
+    /// Expose the WebKit Web Inspector over TCP at ADDR:PORT (default 127.0.0.1:9222)
+    #[arg(long, value_name = "ADDR:PORT", num_args = 0..=1, default_missing_value = "127.0.0.1:9222")]
+    remote: Option<String>,
+
     /// Additional arguments to pass to the target application
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     args: Vec<String>,
 }
 
-fn find_libspy() -> Result<PathBuf, String> {
-    // Check next to the current executable first
-    if let Ok(exe_path) = env::current_exe() {
-        let dir = exe_path.parent().unwrap();
+/// The WebKitGTK ABI a target links against, which decides the libspy variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WebkitAbi {
+    /// webkit2gtk-4.0 (libsoup2)
+    V40,
+    /// webkit2gtk-4.1 (libsoup3)
+    V41,
+}
 
-        // Check in same directory as executable
-        let candidate = dir.join("libspy.so");
-        if candidate.exists() {
-            return Ok(candidate);
+impl WebkitAbi {
+    /// The `<abi>` component of the `libspy-<abi>-<arch>.so` name.
+    fn version(self) -> &'static str {
+        match self {
+            WebkitAbi::V40 => "4.0",
+            WebkitAbi::V41 => "4.1",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            WebkitAbi::V40 => "webkit2gtk-4.0",
+            WebkitAbi::V41 => "webkit2gtk-4.1",
+        }
+    }
+}
+
+/// The CPU architecture of the target binary, which decides the libspy variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TargetArch {
+    /// x86_64 (`e_machine` == 0x3E)
+    X86_64,
+    /// AArch64 (`e_machine` == 0xB7)
+    Aarch64,
+}
+
+impl TargetArch {
+    /// The `<arch>` component of the `libspy-<abi>-<arch>.so` name.
+    fn name(self) -> &'static str {
+        match self {
+            TargetArch::X86_64 => "x86_64",
+            TargetArch::Aarch64 => "aarch64",
+        }
+    }
+
+    fn from_e_machine(e_machine: u16) -> Option<Self> {
+        match e_machine {
+            0x3E => Some(TargetArch::X86_64),
+            0xB7 => Some(TargetArch::Aarch64),
+            _ => None,
+        }
+    }
+}
+
+/// Every ABI tauri-spy knows how to build a variant for.
+const ALL_ABIS: [WebkitAbi; 2] = [WebkitAbi::V40, WebkitAbi::V41];
+/// Every architecture tauri-spy knows how to build a variant for.
+const ALL_ARCHS: [TargetArch; 2] = [TargetArch::X86_64, TargetArch::Aarch64];
+
+/// File name of the libspy variant for a given ABI and architecture.
+fn libspy_so_name(abi: WebkitAbi, arch: TargetArch) -> String {
+    format!("libspy-{}-{}.so", abi.version(), arch.name())
+}
+
+/// Variants compiled into the binary by build.rs: `(abi, arch, bytes)`. Used as a
+/// last-resort fallback when no on-disk copy is found, making tauri-spy a single
+/// relocatable binary.
+include!(concat!(env!("OUT_DIR"), "/embedded_libspy.rs"));
+
+/// Stable per-user install directory for libspy variants
+/// (`$XDG_DATA_HOME/tauri-spy` or `~/.local/share/tauri-spy`).
+fn install_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| !p.as_os_str().is_empty())
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+        .map(|d| d.join("tauri-spy"))
+}
+
+/// Per-user cache directory for extracted libspy variants
+/// (`$XDG_CACHE_HOME/tauri-spy` or `~/.cache/tauri-spy`).
+fn cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|p| !p.as_os_str().is_empty())
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+        .map(|d| d.join("tauri-spy"))
+}
+
+/// FNV-1a hash, used to key extracted variants by content so stale copies are
+/// never reused and concurrent runs converge on the same file name.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Write the embedded bytes for `abi`/`arch` to the cache directory and return
+/// the path, reusing an existing extraction when the content hash matches.
+fn extract_embedded(abi: WebkitAbi, arch: TargetArch) -> Result<PathBuf, String> {
+    let bytes = EMBEDDED_LIBSPY
+        .iter()
+        .find(|(a, ar, _)| *a == abi.version() && *ar == arch.name())
+        .map(|(_, _, bytes)| *bytes)
+        .ok_or_else(|| format!("no embedded {} variant", libspy_so_name(abi, arch)))?;
+
+    let dir = cache_dir().ok_or("cannot determine cache directory (set HOME or XDG_CACHE_HOME)")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+
+    // Content hash in the name avoids stale copies and keeps concurrent runs safe.
+    let file_name = format!("libspy-{}-{}-{:016x}.so", abi.version(), arch.name(), fnv1a(bytes));
+    let dest = dir.join(&file_name);
+    if dest.exists() {
+        return Ok(dest);
+    }
+
+    // Write to a process-unique temp file, then atomically rename into place.
+    let tmp = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    fs::write(&tmp, bytes).map_err(|e| format!("failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, &dest).map_err(|e| format!("failed to install {}: {}", dest.display(), e))?;
+    Ok(dest)
+}
+
+/// Directories searched for libspy variants, in priority order: next to the
+/// executable, the installed `../lib/` layout, then the per-user install dir.
+fn libspy_search_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(exe_path) = env::current_exe() {
+        if let Some(dir) = exe_path.parent() {
+            dirs.push(dir.to_path_buf());
+            dirs.push(dir.join("../lib"));
         }
+    }
+    if let Some(dir) = install_dir() {
+        dirs.push(dir);
+    }
+    dirs
+}
 
-        // Check in ../lib/ (for installed layouts)
-        let candidate = dir.join("../lib/libspy.so");
+fn find_libspy(abi: WebkitAbi, arch: TargetArch) -> Result<PathBuf, String> {
+    let so_name = libspy_so_name(abi, arch);
+
+    for dir in libspy_search_dirs() {
+        let candidate = dir.join(&so_name);
         if candidate.exists() {
-            return Ok(candidate.canonicalize().map_err(|e| e.to_string())?);
+            return candidate.canonicalize().map_err(|e| e.to_string());
         }
     }
 
-    Err("Could not find libspy.so — is it built?".to_string())
+    // Nothing on disk — fall back to the copy embedded in the binary, if any.
+    match extract_embedded(abi, arch) {
+        Ok(path) => Ok(path),
+        Err(embed_err) => Err(format!(
+            "Could not find {so_name} (the {} / {} variant) — is it built?\n  {} no on-disk copy and {}\n  {} build tauri-spy on a system with that WebKitGTK development package and toolchain installed",
+            abi.label(),
+            arch.name(),
+            "hint:".yellow().bold(),
+            embed_err,
+            "hint:".yellow().bold()
+        )),
+    }
 }
 
-fn validate_target(path: &Path) -> Result<(), String> {
+/// Validate the target binary and return the ELF bytes read from disk so callers
+/// can inspect them further without a second read.
+fn validate_target(path: &Path) -> Result<Vec<u8>, String> {
     if !path.exists() {
         return Err(format!("Target binary not found: {}", path.display()));
     }
@@ -81,11 +253,12 @@ fn validate_target(path: &Path) -> Result<(), String> {
         ));
     }
 
-    // Check x86_64 architecture (e_machine == 0x3E at offset 18)
+    // Check supported architecture (e_machine at offset 18): x86_64 (0x3E) or
+    // AArch64 (0xB7).
     let e_machine = u16::from_le_bytes([bytes[18], bytes[19]]);
-    if e_machine != 0x3E {
+    if TargetArch::from_e_machine(e_machine).is_none() {
         return Err(format!(
-            "Target architecture is not x86_64 (e_machine=0x{:X})\n  {} tauri-spy currently only supports x86_64",
+            "Target architecture is unsupported (e_machine=0x{:X})\n  {} tauri-spy supports x86_64 and aarch64",
             e_machine,
             "hint:".yellow().bold()
         ));
@@ -102,31 +275,370 @@ fn validate_target(path: &Path) -> Result<(), String> {
         ));
     }
 
-    Ok(())
+    Ok(bytes)
 }
 
-/// Check if WebKitGTK is available on the system
-fn check_webkit_available() -> bool {
+/// Read a little-endian `u64` at `off`, or `None` if it would run past the end.
+fn read_u64(bytes: &[u8], off: usize) -> Option<u64> {
+    bytes
+        .get(off..off + 8)
+        .map(|s| u64::from_le_bytes(s.try_into().unwrap()))
+}
+
+/// Read a little-endian `u32` at `off`, or `None` if it would run past the end.
+fn read_u32(bytes: &[u8], off: usize) -> Option<u32> {
+    bytes
+        .get(off..off + 4)
+        .map(|s| u32::from_le_bytes(s.try_into().unwrap()))
+}
+
+/// Collect the `DT_NEEDED` SONAMEs of a 64-bit little-endian ELF image.
+///
+/// Walks the program headers to the `PT_DYNAMIC` segment, gathers every
+/// `DT_NEEDED` string-table offset plus the `DT_STRTAB` virtual address, then
+/// translates that address to a file offset through the `PT_LOAD` segments and
+/// reads the NUL-terminated names. Returns an empty vector if the image can't be
+/// parsed — callers fall back to the default ABI in that case.
+fn needed_libraries(bytes: &[u8]) -> Vec<String> {
+    const PT_LOAD: u32 = 1;
+    const PT_DYNAMIC: u32 = 2;
+    const DT_NULL: u64 = 0;
+    const DT_NEEDED: u64 = 1;
+    const DT_STRTAB: u64 = 5;
+    const PHENT: usize = 56; // size of one 64-bit program header
+
+    // A valid ELF64 header is 64 bytes; bail out before reading fixed-offset
+    // fields so a short/malformed image can never panic.
+    if bytes.len() < 64 {
+        return Vec::new();
+    }
+
+    let e_phoff = match read_u64(bytes, 0x20) {
+        Some(v) => v as usize,
+        None => return Vec::new(),
+    };
+    let e_phentsize = u16::from_le_bytes([bytes[0x36], bytes[0x37]]) as usize;
+    let e_phnum = u16::from_le_bytes([bytes[0x38], bytes[0x39]]) as usize;
+    if e_phentsize != PHENT {
+        return Vec::new();
+    }
+
+    // Collect PT_LOAD segments for virtual-address → file-offset translation,
+    // and remember the PT_DYNAMIC segment's file range.
+    let mut loads: Vec<(u64, u64, u64)> = Vec::new(); // (p_vaddr, p_offset, p_filesz)
+    let mut dynamic: Option<(usize, usize)> = None; // (file offset, file size)
+
+    for i in 0..e_phnum {
+        let ph = e_phoff + i * PHENT;
+        let p_type = match read_u32(bytes, ph) {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+        let p_offset = read_u64(bytes, ph + 8).unwrap_or(0);
+        let p_vaddr = read_u64(bytes, ph + 16).unwrap_or(0);
+        let p_filesz = read_u64(bytes, ph + 32).unwrap_or(0);
+
+        match p_type {
+            PT_LOAD => loads.push((p_vaddr, p_offset, p_filesz)),
+            PT_DYNAMIC => dynamic = Some((p_offset as usize, p_filesz as usize)),
+            _ => {}
+        }
+    }
+
+    let (dyn_off, dyn_size) = match dynamic {
+        Some(d) => d,
+        None => return Vec::new(),
+    };
+
+    // Translate a virtual address to a file offset using the PT_LOAD segments.
+    let vaddr_to_offset = |vaddr: u64| -> Option<usize> {
+        for &(p_vaddr, p_offset, p_filesz) in &loads {
+            if vaddr >= p_vaddr && vaddr < p_vaddr + p_filesz {
+                return Some((p_offset + (vaddr - p_vaddr)) as usize);
+            }
+        }
+        None
+    };
+
+    // Walk the dynamic array of (tag, val) pairs.
+    let mut needed_offsets: Vec<u64> = Vec::new();
+    let mut strtab_vaddr: Option<u64> = None;
+    let mut pos = dyn_off;
+    let dyn_end = dyn_off.saturating_add(dyn_size);
+    while pos + 16 <= dyn_end {
+        let tag = match read_u64(bytes, pos) {
+            Some(v) => v,
+            None => break,
+        };
+        let val = read_u64(bytes, pos + 8).unwrap_or(0);
+        match tag {
+            DT_NULL => break,
+            DT_NEEDED => needed_offsets.push(val),
+            DT_STRTAB => strtab_vaddr = Some(val),
+            _ => {}
+        }
+        pos += 16;
+    }
+
+    let strtab_off = match strtab_vaddr.and_then(vaddr_to_offset) {
+        Some(o) => o,
+        None => return Vec::new(),
+    };
+
+    let mut names = Vec::new();
+    for off in needed_offsets {
+        let start = strtab_off + off as usize;
+        if let Some(tail) = bytes.get(start..) {
+            let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+            if let Ok(name) = std::str::from_utf8(&tail[..end]) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Decide which WebKitGTK ABI the target links against from its `DT_NEEDED`
+/// entries. Defaults to 4.1 when no WebKitGTK dependency is visible (e.g. the
+/// app bundles its own or dlopens it).
+fn detect_webkit_abi(bytes: &[u8]) -> WebkitAbi {
+    for name in needed_libraries(bytes) {
+        if name.starts_with("libwebkit2gtk-4.0") {
+            return WebkitAbi::V40;
+        }
+    }
+    WebkitAbi::V41
+}
+
+/// Check if a specific WebKitGTK ABI is known to pkg-config.
+fn webkit_abi_available(abi: WebkitAbi) -> bool {
     Command::new("pkg-config")
-        .args(["--exists", "webkit2gtk-4.1"])
+        .args(["--exists", abi.label()])
         .status()
         .map(|s| s.success())
         .unwrap_or(false)
 }
 
+/// Check if any supported WebKitGTK ABI is available on the system. Either the
+/// 4.0 or the 4.1 development package is enough, since build.rs produces a
+/// variant for whichever is present.
+fn check_webkit_available() -> bool {
+    ALL_ABIS.iter().any(|&abi| webkit_abi_available(abi))
+}
+
+/// Validate a `--remote` address, returning the parsed `ADDR:PORT`.
+fn validate_remote_addr(addr: &str) -> Result<SocketAddr, String> {
+    addr.parse::<SocketAddr>().map_err(|_| {
+        format!(
+            "invalid --remote address {:?} — expected ADDR:PORT, e.g. 127.0.0.1:9222",
+            addr
+        )
+    })
+}
+
+/// Check if a C compiler is available to (re)build libspy.
+fn check_gcc_available() -> bool {
+    Command::new("gcc")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Print a single `doctor` report line: green `ok` / red `fail` plus a detail.
+fn report_line(ok: bool, label: &str, detail: &str) {
+    let mark = if ok {
+        " ok ".black().on_green()
+    } else {
+        "fail".white().on_red()
+    };
+    println!("  [{}] {} — {}", mark, label.bold(), detail.dimmed());
+}
+
+/// Run every environment probe and print a colorized pass/fail report so users
+/// can diagnose "injection did nothing" without reading the source.
+fn doctor() -> ExitCode {
+    println!("{} environment diagnostics", "tauri-spy doctor".cyan().bold());
+
+    let mut ok = true;
+
+    // WebKitGTK development package (needed to build, and usually present at
+    // runtime). Either ABI is sufficient; report which ones pkg-config sees.
+    let abis_found: Vec<&str> = ALL_ABIS
+        .iter()
+        .filter(|&&abi| webkit_abi_available(abi))
+        .map(|&abi| abi.label())
+        .collect();
+    let webkit = !abis_found.is_empty();
+    ok &= webkit;
+    report_line(
+        webkit,
+        "webkitgtk",
+        &if webkit {
+            format!("found via pkg-config: {}", abis_found.join(", "))
+        } else {
+            "neither webkit2gtk-4.0 nor webkit2gtk-4.1 found — install libwebkit2gtk-4.1-dev"
+                .to_string()
+        },
+    );
+
+    // C compiler, required by build.rs to produce libspy.
+    let gcc = check_gcc_available();
+    ok &= gcc;
+    report_line(
+        gcc,
+        "gcc",
+        if gcc {
+            "C compiler available"
+        } else {
+            "gcc missing — install build-essential to rebuild libspy"
+        },
+    );
+
+    // libspy variants discoverable on disk. Check the real search locations
+    // directly — not find_libspy, which would extract an embedded copy into the
+    // cache and mask a genuine "nothing installed" failure.
+    let search_dirs = libspy_search_dirs();
+    let mut found: Vec<String> = Vec::new();
+    for abi in ALL_ABIS {
+        for arch in ALL_ARCHS {
+            let so_name = libspy_so_name(abi, arch);
+            for dir in &search_dirs {
+                let candidate = dir.join(&so_name);
+                if candidate.exists() {
+                    found.push(format!("{} ({})", so_name, candidate.display()));
+                    break;
+                }
+            }
+        }
+    }
+    let has_libspy = !found.is_empty();
+    ok &= has_libspy;
+    if has_libspy {
+        report_line(true, "libspy", &format!("{} variant(s) found", found.len()));
+        for f in &found {
+            println!("         - {}", f.dimmed());
+        }
+    } else {
+        report_line(
+            false,
+            "libspy",
+            "no variant found — run `cargo build --release`, then `tauri-spy install`",
+        );
+    }
+
+    println!();
+    if ok {
+        println!("{} all checks passed", "result:".green().bold());
+        ExitCode::SUCCESS
+    } else {
+        println!("{} some checks failed — see hints above", "result:".red().bold());
+        ExitCode::FAILURE
+    }
+}
+
+/// Copy every libspy variant found next to the executable into the stable
+/// per-user install directory so `find_libspy` keeps working after the cargo
+/// `target/` directory is cleaned or the binary is moved.
+fn install() -> ExitCode {
+    let dest = match install_dir() {
+        Some(d) => d,
+        None => {
+            eprintln!(
+                "{} cannot determine install directory (set HOME or XDG_DATA_HOME)",
+                "error:".red().bold()
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let exe_dir = match env::current_exe().ok().and_then(|e| e.parent().map(|p| p.to_path_buf())) {
+        Some(d) => d,
+        None => {
+            eprintln!("{} cannot locate the tauri-spy executable", "error:".red().bold());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(e) = fs::create_dir_all(&dest) {
+        eprintln!("{} failed to create {}: {}", "error:".red().bold(), dest.display(), e);
+        return ExitCode::FAILURE;
+    }
+
+    let mut installed = 0;
+    for abi in ALL_ABIS {
+        for arch in ALL_ARCHS {
+            let so_name = libspy_so_name(abi, arch);
+            let src = exe_dir.join(&so_name);
+            if !src.exists() {
+                continue;
+            }
+            let dst = dest.join(&so_name);
+            match fs::copy(&src, &dst) {
+                Ok(_) => {
+                    println!("{} {}", "installed".green().bold(), dst.display());
+                    installed += 1;
+                }
+                Err(e) => {
+                    eprintln!("{} failed to copy {}: {}", "error:".red().bold(), so_name, e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
+
+    if installed == 0 {
+        eprintln!(
+            "{} no libspy variants found next to {} — build them first with `cargo build --release`",
+            "error:".red().bold(),
+            exe_dir.display()
+        );
+        return ExitCode::FAILURE;
+    }
+
+    println!(
+        "{} {} variant(s) installed to {}",
+        "tauri-spy".cyan().bold(),
+        installed,
+        dest.display().to_string().green()
+    );
+    ExitCode::SUCCESS
+}
+
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    // Validate target binary
-    if let Err(e) = validate_target(&cli.target) {
-        eprintln!("{} {}", "error:".red().bold(), e);
-        return ExitCode::FAILURE;
+    match cli.command {
+        Some(Commands::Doctor) => doctor(),
+        Some(Commands::Install) => install(),
+        Some(Commands::Run(args)) => run(args),
+        None => run(cli.run),
     }
+}
+
+fn run(cli: RunArgs) -> ExitCode {
+    // Validate target binary and keep the ELF bytes for ABI detection.
+    let bytes = match validate_target(&cli.target) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("{} {}", "error:".red().bold(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Validate the remote inspector address before launch so we fail fast.
+    let remote_addr = match cli.remote.as_deref().map(validate_remote_addr).transpose() {
+        Ok(addr) => addr,
+        Err(e) => {
+            eprintln!("{} {}", "error:".red().bold(), e);
+            return ExitCode::FAILURE;
+        }
+    };
 
     // Check WebKitGTK availability
     if !check_webkit_available() {
         eprintln!(
-            "{} WebKitGTK 4.1 not found on this system",
+            "{} WebKitGTK (4.0 or 4.1) not found on this system",
             "warning:".yellow().bold()
         );
         eprintln!(
@@ -140,8 +652,14 @@ fn main() -> ExitCode {
         );
     }
 
+    // Pick the libspy variant matching the target's WebKitGTK ABI and architecture.
+    let abi = detect_webkit_abi(&bytes);
+    let e_machine = u16::from_le_bytes([bytes[18], bytes[19]]);
+    // validate_target already rejected unsupported machines, so this is Some.
+    let arch = TargetArch::from_e_machine(e_machine).expect("validated above");
+
     // Find the injection library
-    let libspy_path = match find_libspy() {
+    let libspy_path = match find_libspy(abi, arch) {
         Ok(path) => path,
         Err(e) => {
             eprintln!("{} {}", "error:".red().bold(), e);
@@ -155,9 +673,10 @@ fn main() -> ExitCode {
         cli.target.display().to_string().green()
     );
     println!(
-        "{} Injecting {}",
+        "{} Injecting {} ({})",
         "       >>>".cyan(),
-        libspy_path.display().to_string().dimmed()
+        libspy_path.display().to_string().dimmed(),
+        format!("{} / {}", abi.label(), arch.name()).dimmed()
     );
 
     // Build LD_PRELOAD value, preserving any existing preloads
@@ -172,15 +691,28 @@ fn main() -> ExitCode {
     let auto_open = if cli.auto_open { "1" } else { "0" };
 
     // Launch target with LD_PRELOAD and WebKit rendering workarounds
-    let status = Command::new(&cli.target)
+    let mut command = Command::new(&cli.target);
+    command
         .args(&cli.args)
         .env("LD_PRELOAD", &preload)
         .env("TAURI_SPY_AUTO_OPEN", auto_open)
         // Work around WebKitGTK GPU rendering issues (blank/black window)
         // See: https://github.com/nicbarker/clay/issues/213
         .env("WEBKIT_DISABLE_COMPOSITING_MODE", "1")
-        .env("WEBKIT_DISABLE_DMABUF_RENDERER", "1")
-        .status();
+        .env("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+
+    // Expose the remote Web Inspector endpoint. Independent of --auto-open, so the
+    // in-process window and the remote server can both be active.
+    if let Some(addr) = remote_addr {
+        command.env("WEBKIT_INSPECTOR_SERVER", addr.to_string());
+        println!(
+            "{} Remote inspector listening on {}",
+            "       >>>".cyan(),
+            format!("http://{}", addr).green()
+        );
+    }
+
+    let status = command.status();
 
     match status {
         Ok(status) => {
@@ -202,3 +734,103 @@ fn main() -> ExitCode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_u16(buf: &mut [u8], off: usize, v: u16) {
+        buf[off..off + 2].copy_from_slice(&v.to_le_bytes());
+    }
+    fn write_u32(buf: &mut [u8], off: usize, v: u32) {
+        buf[off..off + 4].copy_from_slice(&v.to_le_bytes());
+    }
+    fn write_u64(buf: &mut [u8], off: usize, v: u64) {
+        buf[off..off + 8].copy_from_slice(&v.to_le_bytes());
+    }
+
+    /// Build a minimal ET_DYN ELF64 image whose sole `DT_NEEDED` entry is `soname`.
+    /// A single `PT_LOAD` identity-maps the file so vaddr == file offset.
+    fn elf_with_needed(soname: &str) -> Vec<u8> {
+        const PHOFF: usize = 64;
+        const PHENT: usize = 56;
+        const DYN_OFF: usize = PHOFF + 2 * PHENT; // two program headers
+        const DYN_LEN: usize = 48; // three (tag, val) pairs
+        let strtab_off = DYN_OFF + DYN_LEN;
+
+        // String table: leading NUL, then the soname, then a terminating NUL.
+        let mut strtab = vec![0u8];
+        let name_off = strtab.len() as u64;
+        strtab.extend_from_slice(soname.as_bytes());
+        strtab.push(0);
+
+        let total = strtab_off + strtab.len();
+        let mut buf = vec![0u8; total];
+
+        // ELF header
+        buf[0..4].copy_from_slice(b"\x7fELF");
+        buf[4] = 2; // EI_CLASS = 64-bit
+        buf[5] = 1; // EI_DATA = little-endian
+        buf[6] = 1; // EI_VERSION
+        write_u16(&mut buf, 16, 3); // e_type = ET_DYN
+        write_u16(&mut buf, 18, 0x3E); // e_machine = x86_64
+        write_u64(&mut buf, 0x20, PHOFF as u64); // e_phoff
+        write_u16(&mut buf, 0x36, PHENT as u16); // e_phentsize
+        write_u16(&mut buf, 0x38, 2); // e_phnum
+
+        // PT_LOAD identity-mapping the whole file
+        let ph0 = PHOFF;
+        write_u32(&mut buf, ph0, 1); // p_type = PT_LOAD
+        write_u64(&mut buf, ph0 + 8, 0); // p_offset
+        write_u64(&mut buf, ph0 + 16, 0); // p_vaddr
+        write_u64(&mut buf, ph0 + 32, total as u64); // p_filesz
+
+        // PT_DYNAMIC
+        let ph1 = PHOFF + PHENT;
+        write_u32(&mut buf, ph1, 2); // p_type = PT_DYNAMIC
+        write_u64(&mut buf, ph1 + 8, DYN_OFF as u64); // p_offset
+        write_u64(&mut buf, ph1 + 16, DYN_OFF as u64); // p_vaddr
+        write_u64(&mut buf, ph1 + 32, DYN_LEN as u64); // p_filesz
+
+        // Dynamic array: DT_NEEDED, DT_STRTAB, DT_NULL
+        write_u64(&mut buf, DYN_OFF, 1); // DT_NEEDED
+        write_u64(&mut buf, DYN_OFF + 8, name_off);
+        write_u64(&mut buf, DYN_OFF + 16, 5); // DT_STRTAB
+        write_u64(&mut buf, DYN_OFF + 24, strtab_off as u64);
+        write_u64(&mut buf, DYN_OFF + 32, 0); // DT_NULL
+        write_u64(&mut buf, DYN_OFF + 40, 0);
+
+        buf[strtab_off..strtab_off + strtab.len()].copy_from_slice(&strtab);
+        buf
+    }
+
+    #[test]
+    fn detects_webkit_4_0_soname() {
+        let elf = elf_with_needed("libwebkit2gtk-4.0.so.37");
+        assert_eq!(
+            needed_libraries(&elf),
+            vec!["libwebkit2gtk-4.0.so.37".to_string()]
+        );
+        assert_eq!(detect_webkit_abi(&elf), WebkitAbi::V40);
+    }
+
+    #[test]
+    fn detects_webkit_4_1_soname() {
+        let elf = elf_with_needed("libwebkit2gtk-4.1.so.0");
+        assert_eq!(
+            needed_libraries(&elf),
+            vec!["libwebkit2gtk-4.1.so.0".to_string()]
+        );
+        assert_eq!(detect_webkit_abi(&elf), WebkitAbi::V41);
+    }
+
+    #[test]
+    fn malformed_image_defaults_to_v41_without_panicking() {
+        // Empty, too-short, and truncated-header buffers must all be handled.
+        assert!(needed_libraries(&[]).is_empty());
+        assert_eq!(detect_webkit_abi(&[]), WebkitAbi::V41);
+        assert_eq!(detect_webkit_abi(&[0u8; 8]), WebkitAbi::V41);
+        assert_eq!(detect_webkit_abi(&[0u8; 50]), WebkitAbi::V41);
+        assert_eq!(detect_webkit_abi(b"\x7fELF"), WebkitAbi::V41);
+    }
+}